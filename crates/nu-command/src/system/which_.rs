@@ -1,6 +1,8 @@
 use itertools::Itertools;
 use nu_engine::{command_prelude::*, env};
+use nu_protocol::ast::{Argument, Expr};
 use nu_protocol::engine::CommandType;
+use regex::Regex;
 use std::fs;
 use std::{ffi::OsStr, path::Path};
 use std::path::PathBuf;
@@ -22,6 +24,16 @@ impl Command for Which {
             .allow_variants_without_examples(true)
             .rest("applications", SyntaxShape::String, "Application(s).")
             .switch("all", "list all executables", Some('a'))
+            .switch(
+                "regex",
+                "each application is a regular expression matched against PATH executable names",
+                Some('r'),
+            )
+            .switch(
+                "resolve",
+                "follow an alias's expansion chain to the command or external binary it ultimately runs",
+                None,
+            )
             .category(Category::System)
     }
 
@@ -62,6 +74,16 @@ impl Command for Which {
                 example: "which -a",
                 result: None,
             },
+            Example {
+                description: "List every versioned Python interpreter on PATH",
+                example: r"which -r '^python3\.\d+$'",
+                result: None,
+            },
+            Example {
+                description: "Explain the full shadowing/expansion path of an alias",
+                example: "which -a --resolve ll",
+                result: None,
+            },
         ]
     }
 }
@@ -83,13 +105,139 @@ fn entry(
     )
 }
 
-fn get_entry_in_commands(engine_state: &EngineState, name: &str, span: Span) -> Option<Value> {
-    if let Some(decl_id) = engine_state.find_decl(name.as_bytes(), &[]) {
+/// A row reported for a miss that has a close neighbor, carrying a `suggestion` column
+/// instead of aborting the whole `which` call.
+fn suggestion_entry(prog_name: &str, span: Span, suggestions: Vec<String>) -> Value {
+    Value::record(
+        record! {
+            "command" => Value::string(prog_name, span),
+            "path" => Value::string("", span),
+            "type" => Value::string("", span),
+            "suggestion" => Value::string(format!("did you mean {}?", suggestions.join(", ")), span),
+        },
+        span,
+    )
+}
+
+/// Like [`entry`], but with a `resolved` column showing the alias → command → external
+/// expansion chain `which --resolve` followed to get here.
+fn entry_resolved(
+    arg: impl Into<String>,
+    path: impl Into<String>,
+    cmd_type: CommandType,
+    span: Span,
+    hops: Vec<String>,
+) -> Value {
+    Value::record(
+        record! {
+            "command" => Value::string(arg, span),
+            "path" => Value::string(path, span),
+            "type" => Value::string(cmd_type.to_string(), span),
+            "resolved" => Value::list(
+                hops.into_iter().map(|hop| Value::string(hop, span)).collect(),
+                span,
+            ),
+        },
+        span,
+    )
+}
+
+/// The longest alias chain `which --resolve` will follow before giving up. Aliases can't
+/// normally reference themselves (they capture whatever the name resolved to when
+/// defined), but this keeps a pathological or future self-referential chain bounded.
+const MAX_ALIAS_HOPS: usize = 32;
+
+/// Follows an alias's expansion chain to the command (or external binary) it ultimately
+/// runs, recording each hop along the way. An alias body that calls `^cmd` is parsed as a
+/// call to the internal `run-external` command, so that's treated as the external hop.
+///
+/// Each hop continues from the `decl_id` the alias body actually called, not a re-lookup
+/// by name, so a later redefinition of a hop's name can't change where this chain leads.
+fn resolve_alias_chain(
+    engine_state: &EngineState,
+    name: &str,
+) -> (Vec<String>, CommandType, Option<String>) {
+    let mut hops = Vec::new();
+    let mut cmd_type = CommandType::Builtin;
+    let mut external_target = None;
+
+    let Some(mut decl_id) = engine_state.find_decl(name.as_bytes(), &[]) else {
+        return (hops, cmd_type, external_target);
+    };
+
+    for _ in 0..MAX_ALIAS_HOPS {
         let decl = engine_state.get_decl(decl_id);
-        Some(entry(name, "", decl.command_type(), span))
-    } else {
-        None
+        cmd_type = decl.command_type();
+        if cmd_type != CommandType::Alias {
+            break;
+        }
+
+        let Some(block_id) = decl.block_id() else {
+            break;
+        };
+        let block = engine_state.get_block(block_id);
+        let Some(call) = block
+            .pipelines
+            .first()
+            .and_then(|pipeline| pipeline.elements.first())
+            .and_then(|element| match &element.expr.expr {
+                Expr::Call(call) => Some(call.as_ref()),
+                _ => None,
+            })
+        else {
+            break;
+        };
+
+        let next_decl = engine_state.get_decl(call.decl_id);
+        let next_name = next_decl.name().to_string();
+
+        if next_name == "run-external" {
+            external_target = call.arguments.first().and_then(|arg| match arg {
+                Argument::Positional(expr) => match &expr.expr {
+                    Expr::String(s) | Expr::GlobPattern(s, _) => Some(s.clone()),
+                    _ => None,
+                },
+                _ => None,
+            });
+            hops.push(format!("^{}", external_target.clone().unwrap_or_default()));
+            cmd_type = CommandType::External;
+            break;
+        }
+
+        hops.push(next_name);
+        decl_id = call.decl_id;
+    }
+
+    (hops, cmd_type, external_target)
+}
+
+fn get_entry_in_commands(
+    engine_state: &EngineState,
+    name: &str,
+    span: Span,
+    resolve: bool,
+    cwd: impl AsRef<Path>,
+    paths: impl AsRef<OsStr>,
+) -> Option<Value> {
+    let decl_id = engine_state.find_decl(name.as_bytes(), &[])?;
+    let decl = engine_state.get_decl(decl_id);
+    let cmd_type = decl.command_type();
+
+    if !resolve {
+        return Some(entry(name, "", cmd_type, span));
     }
+
+    // Only an alias has anywhere further to go; a custom command already *is* its target,
+    // so this comes back with an empty hop chain rather than fabricating one.
+    let (hops, final_type, external_target) = resolve_alias_chain(engine_state, name);
+    let path = match external_target {
+        Some(target) => which::which_in(target, Some(paths), cwd)
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    Some(entry_resolved(name, path, final_type, span, hops))
 }
 
 fn get_first_entry_in_path(
@@ -117,6 +265,92 @@ fn get_all_entries_in_path(
         .unwrap_or_default()
 }
 
+/// Walks every directory on `paths` and yields the `(command_name, path)` of each entry
+/// `executable_command_name` considers launchable. This is the one place in this file that
+/// lists PATH directories itself (single-name lookups go through `which::which_in`);
+/// `list_all_executables`, `suggest_candidates` and `get_regex_entries_in_path` all build
+/// on it so a PATH-resolution fix (like `PATHEXT` support) only has to land once for them.
+fn path_executables(paths: impl AsRef<OsStr>) -> impl Iterator<Item = (String, PathBuf)> {
+    sys::RealSys
+        .env_split_paths(paths.as_ref())
+        .into_iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter_map(|path| executable_command_name(&path).map(|name| (name, path)))
+}
+
+/// Treats `pattern` as a regular expression and matches it against the name of every
+/// executable `path_executables` finds on PATH (so PATHEXT stripping on Windows applies
+/// here the same as it does for the other PATH-walking lookups in this file).
+fn get_regex_entries_in_path(pattern: &Spanned<String>, paths: impl AsRef<OsStr>) -> Result<Vec<Value>, ShellError> {
+    let re = Regex::new(&pattern.item).map_err(|err| ShellError::GenericError {
+        error: format!("Invalid regex `{}`", pattern.item),
+        msg: err.to_string(),
+        span: Some(pattern.span),
+        help: None,
+        inner: vec![],
+    })?;
+
+    let matches = path_executables(paths.as_ref())
+        .filter(|(name, _)| re.is_match(name))
+        .map(|(name, path)| entry(name, path.to_string_lossy(), CommandType::External, pattern.span))
+        .collect();
+
+    Ok(matches)
+}
+
+/// Parses a `PATHEXT`-style `;`-separated extension list into lower-cased extensions
+/// without their leading dot. Split out from [`pathext_extensions`] so the parsing can be
+/// unit tested without an actual Windows `PATHEXT` to read.
+#[cfg(any(windows, test))]
+fn parse_pathext(pathext: &str) -> Vec<String> {
+    pathext
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+        .collect()
+}
+
+/// The extensions Windows treats as directly launchable, read from `PATHEXT`
+/// (falling back to the cmd.exe default list), lower-cased and without the leading dot.
+#[cfg(windows)]
+fn pathext_extensions() -> Vec<String> {
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    parse_pathext(&pathext)
+}
+
+/// If `path`'s extension (case-insensitively) is one of `pathext`, returns the file name
+/// with that extension stripped; otherwise `None`. Split out from [`executable_command_name`]
+/// so the stripping logic can be unit tested on any platform.
+#[cfg(any(windows, test))]
+fn strip_pathext(path: &Path, pathext: &[String]) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+    if !pathext.iter().any(|candidate| candidate == &ext) {
+        return None;
+    }
+    path.file_stem().map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// The name a user would actually type to launch `path`, or `None` if PATH resolution
+/// would not consider it a program. On Windows this consults `PATHEXT` and strips the
+/// matched extension, so `foo.cmd` is reported (and deduplicated) as `foo`, matching how
+/// `PATHEXT` makes Windows itself resolve commands. Elsewhere it defers to the executable bit.
+fn executable_command_name(path: &Path) -> Option<String> {
+    #[cfg(windows)]
+    {
+        strip_pathext(path, &pathext_extensions())
+    }
+    #[cfg(not(windows))]
+    {
+        if path.is_executable() {
+            path.file_name().map(|f| f.to_string_lossy().into_owned())
+        } else {
+            None
+        }
+    }
+}
+
 fn list_all_executables(
     engine_state: &EngineState,
     paths: impl AsRef<OsStr>,
@@ -134,23 +368,15 @@ fn list_all_executables(
         .collect();
 
     // Add PATH executables
-    let iter_over_path = sys::RealSys
-        .env_split_paths(paths.as_ref())
-        .into_iter()
-        .filter_map(|dir| fs::read_dir(dir).ok())
-        .flat_map(|entries| entries.flatten())
-        .map(|entry| entry.path());
+    let iter_over_path = path_executables(paths.as_ref());
 
-    let filtered_paths: Vec<PathBuf> = if all {
-        iter_over_path.filter(|path| path.is_executable()).collect()
+    let filtered_paths: Vec<(String, PathBuf)> = if all {
+        iter_over_path.collect()
     } else {
-        iter_over_path.unique_by(|path| path.file_name().map(|f| f.to_os_string()))
-            .filter(|path| path.is_executable())
-            .collect()
+        iter_over_path.unique_by(|(name, _)| name.clone()).collect()
     };
-    results.extend(filtered_paths.into_iter().filter_map(|path| {
-        let filename = path.file_name()?.to_string_lossy().into_owned();
-        Some(entry(filename, path.to_string_lossy().into_owned(), CommandType::External, Span::unknown()))
+    results.extend(filtered_paths.into_iter().map(|(name, path)| {
+        entry(name, path.to_string_lossy().into_owned(), CommandType::External, Span::unknown())
     }));
 
     results
@@ -160,11 +386,58 @@ fn list_all_executables(
 struct WhichArgs {
     applications: Vec<Spanned<String>>,
     all: bool,
+    regex: bool,
+    resolve: bool,
+}
+
+/// Computes the classic dynamic-programming edit distance between `a` and `b`,
+/// rolling a single row instead of allocating a full matrix.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let n = b.len();
+
+    let mut d: Vec<usize> = (0..=n).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut prev = d[0];
+        d[0] = i + 1;
+        for j in 1..=n {
+            let old = d[j];
+            d[j] = (d[j] + 1)
+                .min(d[j - 1] + 1)
+                .min(prev + usize::from(ca != b[j - 1]));
+            prev = old;
+        }
+    }
+    d[n]
+}
+
+/// Finds the names closest to `query` among known decls and PATH executables, for
+/// "did you mean" hints. Returns at most three, sorted by distance then name.
+fn suggest_candidates(query: &str, engine_state: &EngineState, paths: impl AsRef<OsStr>) -> Vec<String> {
+    let decl_names = engine_state
+        .get_decls_sorted(false)
+        .into_iter()
+        .map(|(name, _)| String::from_utf8_lossy(&name).to_string());
+
+    let path_names = path_executables(paths.as_ref()).map(|(name, _)| name);
+
+    let max_distance = std::cmp::max(query.len() / 3, 1);
+    let mut scored: Vec<(usize, String)> = decl_names
+        .chain(path_names)
+        .unique()
+        .map(|name| (levenshtein_distance(query, &name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort_by(|(d1, n1), (d2, n2)| d1.cmp(d2).then_with(|| n1.cmp(n2)));
+
+    scored.into_iter().take(3).map(|(_, name)| name).collect()
 }
 
 fn which_single(
     application: Spanned<String>,
     all: bool,
+    resolve: bool,
     engine_state: &EngineState,
     cwd: impl AsRef<Path>,
     paths: impl AsRef<OsStr>,
@@ -179,29 +452,42 @@ fn which_single(
     //If all is false, we can save some time by only searching for the first matching
     //program
     //This match handles all different cases
-    match (all, external) {
-        (true, true) => get_all_entries_in_path(&prog_name, application.span, cwd, paths),
+    let results = match (all, external) {
+        (true, true) => get_all_entries_in_path(&prog_name, application.span, &cwd, &paths),
         (true, false) => {
             let mut output: Vec<Value> = vec![];
-            if let Some(entry) = get_entry_in_commands(engine_state, &prog_name, application.span) {
+            if let Some(entry) =
+                get_entry_in_commands(engine_state, &prog_name, application.span, resolve, &cwd, &paths)
+            {
                 output.push(entry);
             }
             output.extend(get_all_entries_in_path(
                 &prog_name,
                 application.span,
-                cwd,
-                paths,
+                &cwd,
+                &paths,
             ));
             output
         }
-        (false, true) => get_first_entry_in_path(&prog_name, application.span, cwd, paths)
-            .into_iter()
-            .collect(),
-        (false, false) => get_entry_in_commands(engine_state, &prog_name, application.span)
-            .or_else(|| get_first_entry_in_path(&prog_name, application.span, cwd, paths))
+        (false, true) => get_first_entry_in_path(&prog_name, application.span, &cwd, &paths)
             .into_iter()
             .collect(),
+        (false, false) => {
+            get_entry_in_commands(engine_state, &prog_name, application.span, resolve, &cwd, &paths)
+                .or_else(|| get_first_entry_in_path(&prog_name, application.span, &cwd, &paths))
+                .into_iter()
+                .collect()
+        }
+    };
+
+    if results.is_empty() {
+        let suggestions = suggest_candidates(&prog_name, engine_state, &paths);
+        if !suggestions.is_empty() {
+            return vec![suggestion_entry(&prog_name, application.span, suggestions)];
+        }
     }
+
+    results
 }
 
 fn which(
@@ -213,6 +499,8 @@ fn which(
     let which_args = WhichArgs {
         applications: call.rest(engine_state, stack, 0)?,
         all: call.has_flag(engine_state, stack, "all")?,
+        regex: call.has_flag(engine_state, stack, "regex")?,
+        resolve: call.has_flag(engine_state, stack, "resolve")?,
     };
 
     let mut output = vec![];
@@ -228,13 +516,11 @@ fn which(
     }
 
     for app in which_args.applications {
-        let values = which_single(
-            app,
-            which_args.all,
-            engine_state,
-            &cwd,
-            &paths,
-        );
+        let values = if which_args.regex {
+            get_regex_entries_in_path(&app, &paths)?
+        } else {
+            which_single(app, which_args.all, which_args.resolve, engine_state, &cwd, &paths)
+        };
         output.extend(values);
     }
 
@@ -246,9 +532,92 @@ fn which(
 #[cfg(test)]
 mod test {
     use super::*;
+    use nu_parser::parse;
+    use nu_protocol::engine::StateWorkingSet;
 
     #[test]
     fn test_examples() {
         crate::test_examples(Which)
     }
+
+    /// A fresh engine state with the default language keywords (`alias`, `def`, ...) and
+    /// this crate's commands (including `ignore` and `run-external`) registered, so alias
+    /// bodies referencing either can actually be parsed and resolved.
+    fn engine_state_with(source: &str) -> EngineState {
+        let mut engine_state = nu_cmd_lang::create_default_context();
+        engine_state = crate::add_shell_command_context(engine_state);
+
+        let mut working_set = StateWorkingSet::new(&engine_state);
+        parse(&mut working_set, None, source.as_bytes(), false);
+        let delta = working_set.render();
+        engine_state
+            .merge_delta(delta)
+            .expect("test fixture source should parse cleanly");
+
+        engine_state
+    }
+
+    #[test]
+    fn test_resolve_alias_chain_to_internal_command() {
+        let engine_state = engine_state_with("alias to-ignore = ignore");
+
+        let (hops, cmd_type, external_target) = resolve_alias_chain(&engine_state, "to-ignore");
+        assert_eq!(hops, vec!["ignore".to_string()]);
+        assert_eq!(cmd_type, CommandType::Builtin);
+        assert_eq!(external_target, None);
+    }
+
+    #[test]
+    fn test_resolve_alias_chain_through_another_alias() {
+        let engine_state =
+            engine_state_with("alias inner = ignore\nalias outer = inner");
+
+        let (hops, cmd_type, external_target) = resolve_alias_chain(&engine_state, "outer");
+        assert_eq!(hops, vec!["inner".to_string(), "ignore".to_string()]);
+        assert_eq!(cmd_type, CommandType::Builtin);
+        assert_eq!(external_target, None);
+    }
+
+    #[test]
+    fn test_resolve_alias_chain_to_external() {
+        let engine_state = engine_state_with("alias run-ls = ^ls");
+
+        let (hops, cmd_type, external_target) = resolve_alias_chain(&engine_state, "run-ls");
+        assert_eq!(hops, vec!["^ls".to_string()]);
+        assert_eq!(cmd_type, CommandType::External);
+        assert_eq!(external_target, Some("ls".to_string()));
+    }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("", ""), 0);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        assert_eq!(levenshtein_distance("abc", ""), 3);
+        assert_eq!(levenshtein_distance("same", "same"), 0);
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        // A transposition costs two edits (a deletion and an insertion), not one.
+        assert_eq!(levenshtein_distance("ab", "ba"), 2);
+    }
+
+    #[test]
+    fn test_parse_pathext() {
+        assert_eq!(
+            parse_pathext(".COM;.EXE;.BAT;.CMD"),
+            vec!["com", "exe", "bat", "cmd"]
+        );
+        assert_eq!(parse_pathext(""), Vec::<String>::new());
+        // Empty segments (e.g. a trailing or doubled `;`) are dropped, not kept as "".
+        assert_eq!(parse_pathext(".PS1;;.CMD"), vec!["ps1", "cmd"]);
+    }
+
+    #[test]
+    fn test_strip_pathext() {
+        let pathext = parse_pathext(".COM;.EXE;.BAT;.CMD");
+        assert_eq!(
+            strip_pathext(Path::new("C:/Windows/foo.CMD"), &pathext),
+            Some("foo".to_string())
+        );
+        assert_eq!(strip_pathext(Path::new("/usr/bin/foo.txt"), &pathext), None);
+        assert_eq!(strip_pathext(Path::new("foo"), &pathext), None);
+    }
 }